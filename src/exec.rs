@@ -0,0 +1,39 @@
+//! # sqldb execution backends
+//!
+//! This module provides the `ExecBackend` trait and the backends that
+//! implement it: the crate's own native table (always available) and an
+//! optional `rusqlite`-backed engine enabled by the `sqlite` Cargo feature.
+//!
+//! ## Usage
+//!
+//! `default_backend()` picks the right implementation for how the crate was
+//! built, boxed behind the shared `ExecBackend` trait so `run()` does not
+//! need to know which engine is actually running underneath.
+//!
+//! ```rust
+//! use sqldb::exec;
+//!
+//! let mut backend = exec::default_backend();
+//! ```
+
+pub mod backend;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+pub use backend::{ExecBackend, QueryOutput};
+
+/// Returns the execution backend to use for a fresh session: the crate's
+/// own in-memory/file-backed table, unless the `sqlite` feature is enabled,
+/// in which case an embedded `rusqlite` in-memory database is used instead.
+#[cfg(not(feature = "sqlite"))]
+pub fn default_backend() -> Box<dyn ExecBackend> {
+    Box::new(crate::storage::Store::default())
+}
+
+/// Returns the execution backend to use for a fresh session. With the
+/// `sqlite` feature enabled, this is an embedded `rusqlite` in-memory
+/// database rather than the crate's own native table.
+#[cfg(feature = "sqlite")]
+pub fn default_backend() -> Box<dyn ExecBackend> {
+    Box::new(sqlite::SqliteBackend::new().expect("Failed to open in-memory sqlite database"))
+}
@@ -0,0 +1,54 @@
+//! # sqldb execution backend trait
+//!
+//! This module defines the `ExecBackend` trait that every SQL execution engine
+//! implements, and the `QueryOutput` it produces for a successfully executed
+//! statement.
+//!
+//! ## Usage
+//!
+//! `run()` holds a single `Box<dyn ExecBackend>`, obtained from
+//! `exec::default_backend()`, and routes every parsed `Statement` through it.
+//! This keeps the REPL and the parser free of any knowledge of how a
+//! statement is actually carried out, so a statement can be executed by the
+//! crate's own in-memory/file-backed table or by an embedded `rusqlite`
+//! database, depending on which backend is active.
+
+use crate::sql::parser::{CommandError, Statement};
+
+/// The outcome of successfully executing a `Statement`.
+///
+/// Both variants are unit-like: a backend prints any rows a `Select`
+/// produces itself (mirroring how `storage::Store::select` already works),
+/// so `QueryOutput` only tells the caller which kind of statement ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryOutput {
+    /// An `insert` statement was executed successfully.
+    Inserted,
+    /// A `select` statement was executed successfully.
+    Selected,
+}
+
+/// A pluggable SQL execution engine.
+///
+/// Implementors own however they store their rows (an in-memory table, a
+/// file-backed pager, an embedded database connection, ...) and are
+/// responsible for carrying out `Statement`s and the `.open FILENAME` /
+/// `.close` meta commands against that storage.
+pub trait ExecBackend {
+    /// Executes a single parsed statement.
+    fn execute(&mut self, stmt: &Statement) -> Result<QueryOutput, CommandError>;
+
+    /// Switches to a persistent database backed by `path`, as requested by
+    /// `.open FILENAME`.
+    fn open(&mut self, path: &str) -> Result<(), CommandError>;
+
+    /// Reverts to a transient, in-memory database, as requested by `.close`.
+    fn close(&mut self) -> Result<(), CommandError>;
+
+    /// Flushes any buffered state to durable storage. Called once, when the
+    /// REPL exits. Backends with nothing to flush can keep the default
+    /// no-op implementation.
+    fn flush(&mut self) -> Result<(), CommandError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+//! # sqldb `rusqlite` execution backend
+//!
+//! This module provides `SqliteBackend`, an `ExecBackend` implementation that
+//! hands statements off to an embedded `rusqlite` database instead of the
+//! crate's own `storage::Table`/`Pager`. It is only compiled when the crate
+//! is built with the `sqlite` feature.
+//!
+//! Every `SqliteBackend` works against a single `users (id, username, email)`
+//! table, matching the fixed schema the rest of the crate assumes, so a
+//! `Row` round-trips the same way regardless of which backend is active.
+
+use crate::exec::{ExecBackend, QueryOutput};
+use crate::sql::parser::{CommandError, Statement};
+use crate::storage::Row;
+use rusqlite::{params, Connection};
+
+const CREATE_USERS_TABLE: &str =
+    "CREATE TABLE IF NOT EXISTS users (id INTEGER PRIMARY KEY, username TEXT, email TEXT)";
+
+/// An `ExecBackend` that executes statements against an embedded `rusqlite`
+/// database: in-memory by default, or file-backed after `.open FILENAME`.
+pub struct SqliteBackend {
+    conn: Connection,
+}
+
+impl SqliteBackend {
+    /// Opens a fresh in-memory `rusqlite` database with the `users` table
+    /// already created.
+    pub fn new() -> rusqlite::Result<Self> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute(CREATE_USERS_TABLE, [])?;
+        Ok(Self { conn })
+    }
+}
+
+impl ExecBackend for SqliteBackend {
+    fn execute(&mut self, stmt: &Statement) -> Result<QueryOutput, CommandError> {
+        match stmt {
+            Statement::Insert(row) => {
+                self.conn
+                    .execute(
+                        "INSERT INTO users (id, username, email) VALUES (?1, ?2, ?3)",
+                        params![row.id, row.username_str(), row.email_str()],
+                    )
+                    .map_err(|err| CommandError::ExecutionFailed(err.to_string()))?;
+                Ok(QueryOutput::Inserted)
+            }
+            Statement::Select(projection) => {
+                let mut statement = self
+                    .conn
+                    .prepare("SELECT id, username, email FROM users")
+                    .map_err(|err| CommandError::ExecutionFailed(err.to_string()))?;
+
+                let rows = statement
+                    .query_map([], |row| {
+                        Ok(Row::new(
+                            row.get(0)?,
+                            &row.get::<_, String>(1)?,
+                            &row.get::<_, String>(2)?,
+                        ))
+                    })
+                    .map_err(|err| CommandError::ExecutionFailed(err.to_string()))?;
+
+                for row in rows {
+                    match row.map_err(|err| CommandError::ExecutionFailed(err.to_string()))? {
+                        Ok(row) => {
+                            let line =
+                                row.format_projected(projection.as_deref())
+                                    .map_err(|name| {
+                                        CommandError::ExecutionFailed(format!(
+                                            "unknown column '{}'",
+                                            name
+                                        ))
+                                    })?;
+                            println!("{}", line);
+                        }
+                        Err(err) => return Err(CommandError::ExecutionFailed(err.to_string())),
+                    }
+                }
+
+                Ok(QueryOutput::Selected)
+            }
+        }
+    }
+
+    fn open(&mut self, path: &str) -> Result<(), CommandError> {
+        let conn =
+            Connection::open(path).map_err(|err| CommandError::OpenFailed(err.to_string()))?;
+        conn.execute(CREATE_USERS_TABLE, [])
+            .map_err(|err| CommandError::OpenFailed(err.to_string()))?;
+        self.conn = conn;
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), CommandError> {
+        let conn = Connection::open_in_memory()
+            .map_err(|err| CommandError::OpenFailed(err.to_string()))?;
+        conn.execute(CREATE_USERS_TABLE, [])
+            .map_err(|err| CommandError::OpenFailed(err.to_string()))?;
+        self.conn = conn;
+        Ok(())
+    }
+}
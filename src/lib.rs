@@ -1,7 +1,9 @@
+pub mod exec;
 pub mod sql {
     pub mod parser;
     pub mod tokenizer;
 }
+pub mod storage;
 pub mod utils;
 
 use crate::{
@@ -9,9 +11,18 @@ use crate::{
         parser::{run_command, Command},
         tokenizer::InputBuffer,
     },
-    utils::{print_db_details, print_prompt},
+    utils::print_db_details,
 };
 
+/// Returns `true` once `buffer` holds a complete statement.
+///
+/// Meta commands (starting with `.`) are always complete as soon as they're
+/// read. SQL statements are only complete once a terminating `;` has been
+/// seen, which lets a single statement span multiple lines.
+fn is_complete(buffer: &str) -> bool {
+    buffer.starts_with('.') || buffer.trim_end().ends_with(';')
+}
+
 /// Runs the SQLDB application.
 ///
 /// This function initializes the necessary components, such as the input buffer and command objects,
@@ -24,18 +35,25 @@ use crate::{
 pub fn run() -> std::io::Result<()> {
     let mut input_buffer = InputBuffer::new();
     let mut command = Command::new();
+    let mut backend = exec::default_backend();
 
     print_db_details();
 
     loop {
-        print_prompt()?;
         input_buffer.read_input()?;
 
+        while !is_complete(input_buffer.buffer.as_deref().unwrap_or_default()) {
+            input_buffer.read_input()?;
+        }
+
         if input_buffer.buffer == Some(".exit".to_string()) {
+            backend.flush().ok();
+            input_buffer.save_history();
             break;
         }
 
-        run_command(&input_buffer, &mut command);
+        run_command(&input_buffer, &mut command, backend.as_mut());
+        input_buffer.clear_pending();
     }
 
     Ok(())
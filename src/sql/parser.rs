@@ -4,18 +4,20 @@
 //!
 //! ## Usage
 //!
-//! The `run_command` function is the main entry point for executing SQL and Meta commands. It takes an input buffer and a mutable `Command` struct as parameters. The input buffer contains the command to be parsed and executed, while the `Command` struct holds the parsed command for execution.
+//! The `run_command` function is the main entry point for executing SQL and Meta commands. It takes an input buffer, a mutable `Command` struct, and the active `ExecBackend` to read from/write to as parameters. The input buffer contains the command to be parsed and executed, while the `Command` struct holds the parsed command for execution.
 //!
 //! ## Examples
 //!
-//! ```rust
+//! ```rust,no_run
 //! use sqldb::sql::tokenizer::InputBuffer;
 //! use sqldb::sql::parser::{run_command, Command};
+//! use sqldb::exec;
 //!
 //! let input_buffer = InputBuffer::new();
 //! let mut command = Command::new();
+//! let mut backend = exec::default_backend();
 //!
-//! run_command(&input_buffer, &mut command);
+//! run_command(&input_buffer, &mut command, backend.as_mut());
 //! ```
 //!
 //! ## Structs
@@ -41,25 +43,31 @@
 //! #### Variants
 //!
 //! - `MetaCommand(MetaCommand)`: Represents a meta command.
-//! - `Query(Query)`: Represents a SQL query command.
+//! - `Query(Statement)`: Represents a SQL statement.
 //!
 //! ### `MetaCommand`
 //!
-//! An enum representing the different types of meta commands.
+//! An enum representing the different types of meta commands, built by the
+//! `combine`-based `meta_command` parser.
 //!
 //! #### Variants
 //!
 //! - `Exit`: Represents the exit meta command.
-//! - `Help`: Represents the help meta command.
+//! - `Help(Vec<String>)`: Represents the help meta command, with any extra arguments.
+//! - `Open(String)`: Opens the given filename as a persistent database.
+//! - `Close`: Closes the file-backed database, reverting to in-memory mode.
+//! - `History`: Prints the lines entered so far in this session.
 //!
-//! ### `Query`
+//! ### `Statement`
 //!
-//! An enum representing the different types of SQL queries.
+//! An enum representing the different kinds of SQL statements, built by the
+//! `combine`-based `insert_statement`/`select_statement` parsers and handed
+//! to an `ExecBackend` for execution.
 //!
 //! #### Variants
 //!
-//! - `Insert`: Represents an INSERT query.
-//! - `Select`: Represents a SELECT query.
+//! - `Insert(Box<Row>)`: Represents an INSERT statement, carrying the parsed row.
+//! - `Select(Option<Vec<String>>)`: Represents a SELECT statement, with an optional column projection.
 //!
 //! ### `CommandError`
 //!
@@ -71,6 +79,10 @@
 //! - `InvalidBuffer`: The input buffer is invalid.
 //! - `UnrecognizedMetaCommand(String)`: The meta command is not recognized.
 //! - `UnrecognizedQuery(String)`: The SQL query is not recognized.
+//! - `ParseError(String)`: The command text could not be parsed.
+//! - `TableFull`: The table has no room left for more rows.
+//! - `OpenFailed(String)`: The `.open FILENAME` command could not open the backing file.
+//! - `ExecutionFailed(String)`: An `ExecBackend` failed to execute a statement.
 //!
 //! ## Functions
 //!
@@ -78,14 +90,16 @@
 //!
 //! Executes a command by parsing and executing it.
 //!
-//! ```rust
+//! ```rust,no_run
 //! use sqldb::sql::tokenizer::InputBuffer;
 //! use sqldb::sql::parser::{run_command, Command};
+//! use sqldb::exec;
 //!
 //! let input_buffer = InputBuffer::new();
 //! let mut command = Command::new();
+//! let mut backend = exec::default_backend();
 //!
-//! run_command(&input_buffer, &mut command);
+//! run_command(&input_buffer, &mut command, backend.as_mut());
 //! ```
 //!
 //! ### `parse_command`
@@ -105,12 +119,22 @@
 //! The `CommandError` enum represents the possible errors that can occur when processing a command. It provides descriptive error messages for each error variant.
 //!
 
+use crate::exec::ExecBackend;
 use crate::sql::tokenizer::InputBuffer;
+use crate::storage::Row;
+use combine::parser::char::{alpha_num, char, letter, spaces, string};
+use combine::parser::combinator::not_followed_by;
+use combine::stream::easy;
+use combine::{many1, optional, satisfy, sep_end_by, EasyParser, Parser};
 use std::process;
 
-pub fn run_command(input_buffer: &InputBuffer, command: &mut Command) {
+pub fn run_command(
+    input_buffer: &InputBuffer,
+    command: &mut Command,
+    backend: &mut dyn ExecBackend,
+) {
     match parse_command(input_buffer, command) {
-        Ok(()) => match execute_command(command) {
+        Ok(()) => match execute_command(command, backend, input_buffer) {
             Ok(()) => {}
             Err(err) => {
                 eprintln!("Error executing command: {}", err);
@@ -129,14 +153,28 @@ pub fn run_command(input_buffer: &InputBuffer, command: &mut Command) {
             CommandError::UnrecognizedQuery(query) => {
                 eprintln!("Unrecognized query: '{}'.", query);
             }
+            CommandError::ParseError(reason) => {
+                eprintln!("Could not parse command: {}.", reason);
+            }
+            CommandError::TableFull => {
+                eprintln!("Table is full.");
+            }
+            CommandError::OpenFailed(reason) => {
+                eprintln!("Could not open database: {}.", reason);
+            }
+            CommandError::ExecutionFailed(reason) => {
+                eprintln!("Could not execute statement: {}.", reason);
+            }
         },
     }
 }
 
 type CommandResult<T> = Result<T, CommandError>;
 
-/// Represents the possible errors that can occur when processing a SQL command.
-enum CommandError {
+/// Represents the possible errors that can occur when processing a SQL command,
+/// including errors raised by an `ExecBackend` while executing a `Statement`.
+#[derive(Debug)]
+pub enum CommandError {
     /// The input buffer is empty.
     EmptyBuffer,
     /// The input buffer is invalid.
@@ -145,26 +183,42 @@ enum CommandError {
     UnrecognizedMetaCommand(String),
     /// The SQL query is not recognized.
     UnrecognizedQuery(String),
+    /// The command text could not be parsed.
+    ParseError(String),
+    /// The table has no room left for more rows.
+    TableFull,
+    /// The `.open FILENAME` command could not open the backing file.
+    OpenFailed(String),
+    /// A backend failed to execute a statement.
+    ExecutionFailed(String),
 }
 
 /// Represents the different types of meta commands.
 enum MetaCommand {
     /// Exit the SQL shell.
     Exit,
-    /// Display help information.
-    Help,
+    /// Display help information, along with any extra arguments passed.
+    Help(Vec<String>),
+    /// Open `FILENAME` as a persistent, file-backed database.
+    Open(String),
+    /// Close the file-backed database and revert to transient in-memory mode.
+    Close,
+    /// Print the lines entered so far in this session.
+    History,
     // Add more meta commands as needed
 }
 
-enum Query {
-    Insert,
-    Select,
-    // Add more SQL queries as needed
+/// A parsed SQL statement, ready to be handed to an `ExecBackend`.
+#[derive(Debug)]
+pub enum Statement {
+    Insert(Box<Row>),
+    Select(Option<Vec<String>>),
+    // Add more SQL statements as needed
 }
 
 enum CommandType {
     MetaCommand(MetaCommand),
-    Query(Query),
+    Query(Statement),
 }
 
 #[derive(Default)]
@@ -172,6 +226,105 @@ pub struct Command {
     variant: Option<CommandType>,
 }
 
+/// Parses a single whitespace-delimited argument token.
+fn argument<Input>() -> impl Parser<Input, Output = String>
+where
+    Input: combine::Stream<Token = char>,
+{
+    many1(satisfy(|c: char| !c.is_whitespace()))
+}
+
+/// Parses a leading `.`, a command name, and its whitespace-separated
+/// arguments, e.g. `.open mydb.sqlite`.
+fn meta_command<Input>() -> impl Parser<Input, Output = (String, Vec<String>)>
+where
+    Input: combine::Stream<Token = char>,
+{
+    (
+        char('.'),
+        many1(letter()),
+        spaces(),
+        sep_end_by(argument(), spaces()),
+    )
+        .map(|(_, name, _, args): (char, String, (), Vec<String>)| (name, args))
+}
+
+fn parse_meta_command(buffer_content: &str) -> CommandResult<MetaCommand> {
+    let ((name, mut args), _) = meta_command()
+        .easy_parse(buffer_content)
+        .map_err(|err: easy::Errors<char, &str, _>| {
+            CommandError::ParseError(format!("{}", err))
+        })?;
+
+    match name.as_str() {
+        "exit" => Ok(MetaCommand::Exit),
+        "help" => Ok(MetaCommand::Help(args)),
+        "close" => Ok(MetaCommand::Close),
+        "history" => Ok(MetaCommand::History),
+        "open" if !args.is_empty() => Ok(MetaCommand::Open(args.remove(0))),
+        "open" => Err(CommandError::ParseError(
+            "expected a filename after .open".to_string(),
+        )),
+        _ => Err(CommandError::UnrecognizedMetaCommand(
+            buffer_content.to_string(),
+        )),
+    }
+}
+
+/// Parses `insert <id> <username> <email>` into its raw `(id, username,
+/// email)` arguments; the caller validates and builds the `Row`.
+fn insert_statement<Input>() -> impl Parser<Input, Output = (String, String, String)>
+where
+    Input: combine::Stream<Token = char>,
+{
+    let id = many1(satisfy(|c: char| c.is_ascii_digit()));
+
+    (
+        string("insert"),
+        spaces(),
+        id,
+        spaces(),
+        argument(),
+        spaces(),
+        argument(),
+    )
+        .map(
+            |(_, _, id, _, username, _, email): (_, _, String, _, String, _, String)| {
+                (id, username, email)
+            },
+        )
+}
+
+/// Parses `select [col1, col2, ...]` into an optional column projection.
+fn select_statement<Input>() -> impl Parser<Input, Output = Option<Vec<String>>>
+where
+    Input: combine::Stream<Token = char>,
+{
+    let column = many1(satisfy(|c: char| c.is_alphanumeric() || c == '_'));
+    let separator = spaces().skip(optional(char(','))).skip(spaces());
+    let projection = sep_end_by(column, separator);
+
+    (string("select"), spaces(), projection).map(
+        |(_, _, columns): (_, _, Vec<String>)| {
+            if columns.is_empty() {
+                None
+            } else {
+                Some(columns)
+            }
+        },
+    )
+}
+
+/// Returns whether `buffer_content` begins with `keyword` followed by
+/// whitespace or the end of the buffer, so e.g. `selectx` isn't mistaken for
+/// a `select` statement merely because it shares a prefix with the keyword.
+fn starts_with_keyword(buffer_content: &str, keyword: &'static str) -> bool {
+    string(keyword)
+        .skip(not_followed_by(alpha_num()))
+        .easy_parse(buffer_content)
+        .is_ok()
+}
+
 fn parse_command(input_buffer: &InputBuffer, command: &mut Command) -> CommandResult<()> {
     let buffer_content = input_buffer
         .buffer
@@ -182,60 +335,77 @@ fn parse_command(input_buffer: &InputBuffer, command: &mut Command) -> CommandRe
         return Err(CommandError::InvalidBuffer);
     }
 
+    command.variant = Some(parse_command_type(buffer_content)?);
+    Ok(())
+}
+
+/// Parses a trimmed, semicolon-free buffer into a `CommandType`. Split out
+/// of `parse_command` so the parsing logic can be exercised directly,
+/// without going through a live `InputBuffer`.
+fn parse_command_type(buffer_content: &str) -> CommandResult<CommandType> {
+    // SQL statements are terminated with a trailing `;` (see `is_complete` in
+    // `lib.rs`); it carries no meaning for the parsers below.
+    let buffer_content = buffer_content.trim_end().trim_end_matches(';');
+
     if buffer_content.starts_with('.') {
-        if let Some(meta_command) = parse_meta_command(buffer_content) {
-            command.variant = Some(CommandType::MetaCommand(meta_command));
-            Ok(())
-        } else {
-            Err(CommandError::UnrecognizedMetaCommand(
-                buffer_content.to_string(),
-            ))
-        }
-    } else if buffer_content.starts_with("select") {
-        command.variant = Some(CommandType::Query(Query::Select));
-        Ok(())
-    } else if buffer_content.starts_with("insert") {
-        command.variant = Some(CommandType::Query(Query::Insert));
-        Ok(())
+        let meta_command = parse_meta_command(buffer_content)?;
+        Ok(CommandType::MetaCommand(meta_command))
+    } else if starts_with_keyword(buffer_content, "insert") {
+        let ((id, username, email), _) = insert_statement()
+            .easy_parse(buffer_content)
+            .map_err(|err: easy::Errors<char, &str, _>| {
+                CommandError::ParseError(format!("{}", err))
+            })?;
+        let id: u32 = id
+            .parse()
+            .map_err(|_| CommandError::ParseError(format!("'{}' is not a valid id", id)))?;
+        let row = Row::new(id, &username, &email)
+            .map_err(|err| CommandError::ParseError(err.to_string()))?;
+        Ok(CommandType::Query(Statement::Insert(Box::new(row))))
+    } else if starts_with_keyword(buffer_content, "select") {
+        let (projection, _) = select_statement()
+            .easy_parse(buffer_content)
+            .map_err(|err: easy::Errors<char, &str, _>| {
+                CommandError::ParseError(format!("{}", err))
+            })?;
+        Ok(CommandType::Query(Statement::Select(projection)))
     } else {
         Err(CommandError::UnrecognizedQuery(buffer_content.to_string()))
     }
 }
 
-fn execute_command(command: &Command) -> CommandResult<()> {
+fn execute_command(
+    command: &Command,
+    backend: &mut dyn ExecBackend,
+    input_buffer: &InputBuffer,
+) -> CommandResult<()> {
     match &command.variant {
         Some(CommandType::MetaCommand(meta_command)) => match meta_command {
-            MetaCommand::Exit => process::exit(0),
-            MetaCommand::Help => {
+            MetaCommand::Exit => {
+                backend.flush().ok();
+                input_buffer.save_history();
+                process::exit(0);
+            }
+            MetaCommand::Help(_args) => {
                 println!(".help command executed.");
                 Ok(())
-            } // Handle more meta commands as needed
-        },
-        Some(CommandType::Query(query)) => match query {
-            Query::Insert => {
-                println!("This is where we would do an insert.");
-                Ok(())
             }
-            Query::Select => {
-                println!("This is where we would do a select.");
+            MetaCommand::Open(path) => backend.open(path),
+            MetaCommand::Close => backend.close(),
+            MetaCommand::History => {
+                for (index, line) in input_buffer.history().iter().enumerate() {
+                    println!("{:>4}  {}", index + 1, line);
+                }
                 Ok(())
-            } // Handle more SQL queries as needed
+            } // Handle more meta commands as needed
         },
+        Some(CommandType::Query(statement)) => backend.execute(statement).map(|_output| ()),
         None => Err(CommandError::UnrecognizedQuery(
             "Unrecognized command".to_string(),
         )),
     }
 }
 
-fn parse_meta_command(buffer_content: &str) -> Option<MetaCommand> {
-    match buffer_content {
-        ".exit" => Some(MetaCommand::Exit),
-        ".help" => Some(MetaCommand::Help),
-        // Handle more meta commands here
-        _ => None,
-    }
-}
-
 impl Command {
     pub fn new() -> Self {
         Self::default()
@@ -251,7 +421,77 @@ impl std::fmt::Display for CommandError {
                 write!(f, "Unrecognized meta command: '{}'.", cmd)
             }
             CommandError::UnrecognizedQuery(query) => write!(f, "Unrecognized query: '{}'.", query),
+            CommandError::ParseError(reason) => write!(f, "Could not parse: {}.", reason),
+            CommandError::TableFull => write!(f, "Table is full."),
+            CommandError::OpenFailed(reason) => write!(f, "Could not open database: {}.", reason),
+            CommandError::ExecutionFailed(reason) => write!(f, "Could not execute: {}.", reason),
             // Add custom error messages for more error variants
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_insert_statement() {
+        match parse_command_type("insert 1 alice alice@example.com").unwrap() {
+            CommandType::Query(Statement::Insert(row)) => {
+                assert_eq!(row.id, 1);
+                assert_eq!(row.username_str(), "alice");
+                assert_eq!(row.email_str(), "alice@example.com");
+            }
+            _ => panic!("expected an Insert statement"),
+        }
+    }
+
+    #[test]
+    fn parses_select_statement_without_projection() {
+        match parse_command_type("select").unwrap() {
+            CommandType::Query(Statement::Select(projection)) => {
+                assert_eq!(projection, None);
+            }
+            _ => panic!("expected a Select statement"),
+        }
+    }
+
+    #[test]
+    fn parses_select_statement_with_projection() {
+        match parse_command_type("select username, email").unwrap() {
+            CommandType::Query(Statement::Select(projection)) => {
+                assert_eq!(
+                    projection,
+                    Some(vec!["username".to_string(), "email".to_string()])
+                );
+            }
+            _ => panic!("expected a Select statement"),
+        }
+    }
+
+    #[test]
+    fn parses_meta_command() {
+        match parse_command_type(".exit").unwrap() {
+            CommandType::MetaCommand(MetaCommand::Exit) => {}
+            _ => panic!("expected the Exit meta command"),
+        }
+    }
+
+    #[test]
+    fn insert_requires_a_word_boundary() {
+        // "insertx" shares a prefix with "insert" but is not the keyword.
+        assert!(matches!(
+            parse_command_type("insertx 1 alice alice@example.com"),
+            Err(CommandError::UnrecognizedQuery(_))
+        ));
+    }
+
+    #[test]
+    fn select_requires_a_word_boundary() {
+        // "selectx" shares a prefix with "select" but is not the keyword.
+        assert!(matches!(
+            parse_command_type("selectx"),
+            Err(CommandError::UnrecognizedQuery(_))
+        ));
+    }
+}
@@ -5,13 +5,15 @@
 //! ## Usage
 //!
 //! The module provides an `InputBuffer` struct, which represents the standard input buffer.
+//! It is backed by a `linefeed` reader, so input supports cursor movement and up-arrow
+//! history recall, on top of the plain `read_input()` API used by the rest of the crate.
 //!
 //! The `read_input()` function reads a line of input from the user and updates the `InputBuffer`
 //! accordingly.
 //!
 //! ## Examples
 //!
-//! ```rust
+//! ```rust,no_run
 //! use sqldb::sql::tokenizer::InputBuffer;
 //!
 //! fn main() -> std::io::Result<()> {
@@ -23,40 +25,81 @@
 //! }
 //! ```
 
-use std::io::{self, BufRead};
+use crate::utils::METADATA;
+use linefeed::{DefaultTerminal, Interface, ReadResult};
+use std::path::PathBuf;
+
+const MAIN_PROMPT: &str = " > ";
+const CONTINUATION_PROMPT: &str = "   ...> ";
 
 /// Represent an input buffer.
 pub struct InputBuffer {
     pub buffer: Option<String>,
     pub buffer_length: usize,
+    /// The statement text accumulated so far, across possibly several calls
+    /// to `read_input`, until it is complete and dispatched.
+    pending: String,
+    /// Readline-style reader providing cursor movement and history recall.
+    interface: Interface<DefaultTerminal>,
+    /// Every non-empty line entered so far, for the `.history` meta command.
+    history: Vec<String>,
 }
 
 impl Default for InputBuffer {
-    /// Returns a default instance of the InputBuffer.
     fn default() -> Self {
-        Self {
-            buffer: None,
-            buffer_length: 0,
-        }
+        Self::new()
     }
 }
 
 impl InputBuffer {
     /// Creates a new `InputBuffer` instance.
     ///
+    /// This builds a `linefeed` reader using the program name from
+    /// `utils::METADATA` and, if present, loads previously saved history from
+    /// `~/.sqldb_history`. `linefeed` needs a real terminal; if stdin/stdout
+    /// isn't one (e.g. piped input), this prints an error and exits with
+    /// status 1 rather than panicking.
+    ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
     /// use sqldb::sql::tokenizer::InputBuffer;
     ///
     /// let input_buffer = InputBuffer::new();
     /// ```
     pub fn new() -> Self {
-        Self::default()
+        let interface = Interface::new(METADATA.name.clone()).unwrap_or_else(|error| {
+            eprintln!("Error initializing input interface: {}", error);
+            std::process::exit(1);
+        });
+
+        if let Err(error) = interface.set_prompt(&format!("{}{}", METADATA.name, MAIN_PROMPT)) {
+            eprintln!("Error setting prompt: {}", error);
+            std::process::exit(1);
+        }
+
+        if let Some(path) = history_path() {
+            // Loading previous history is best-effort: a missing or corrupt
+            // history file should not prevent the shell from starting.
+            let _ = interface.load_history(&path);
+        }
+
+        Self {
+            buffer: None,
+            buffer_length: 0,
+            pending: String::new(),
+            interface,
+            history: Vec::new(),
+        }
     }
 
     /// Reads a line of input from the user and updates the `InputBuffer` accordingly.
     ///
+    /// The prompt shown depends on whether a statement is already in progress: a
+    /// fresh statement gets the main prompt, while a statement spanning
+    /// multiple lines gets the continuation prompt. Reaching end-of-file (e.g.
+    /// Ctrl-D) behaves like entering `.exit`.
+    ///
     /// # Errors
     ///
     /// Returns an `io::Result` indicating whether the input was read successfully or if an error
@@ -64,7 +107,7 @@ impl InputBuffer {
     ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
     /// # use sqldb::sql::tokenizer::InputBuffer;
     /// #
     /// fn main() -> std::io::Result<()> {
@@ -75,19 +118,64 @@ impl InputBuffer {
     /// }
     /// ```
     pub fn read_input(&mut self) -> std::io::Result<()> {
-        let stdin = io::stdin();
-        let mut buffer = String::new();
+        let prompt = if self.pending.is_empty() {
+            format!("{}{}", METADATA.name, MAIN_PROMPT)
+        } else {
+            CONTINUATION_PROMPT.to_string()
+        };
+        self.interface.set_prompt(&prompt)?;
 
-        stdin.lock().read_line(&mut buffer)?;
+        let line = match self.interface.read_line()? {
+            ReadResult::Input(line) => line,
+            ReadResult::Eof => {
+                self.buffer = Some(".exit".to_string());
+                self.buffer_length = self.buffer.as_ref().map(|s| s.len()).unwrap_or(0);
+                return Ok(());
+            }
+            // Ctrl-C and other signals are treated as an empty line rather
+            // than interrupting the REPL loop.
+            ReadResult::Signal(_) => String::new(),
+        };
 
-        self.buffer = Some(buffer.trim().to_string());
-        self.buffer_length = self.buffer.as_ref().map(|s| s.len()).unwrap_or(0);
+        let line = line.trim().to_string();
+        if !line.is_empty() {
+            self.interface.add_history_unique(line.clone());
+            self.history.push(line.clone());
+        }
+
+        if !self.pending.is_empty() {
+            self.pending.push(' ');
+        }
+        self.pending.push_str(&line);
 
-        // if self.buffer_length == 0 {
-        //     eprintln!("No input provided");
-        //     std::process::exit(1);
-        // }
+        self.buffer = Some(self.pending.clone());
+        self.buffer_length = self.buffer.as_ref().map(|s| s.len()).unwrap_or(0);
 
         Ok(())
     }
+
+    /// Clears the pending statement buffer, e.g. after a complete statement
+    /// has been dispatched.
+    pub fn clear_pending(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Returns the lines entered so far, most recent last, for the
+    /// `.history` meta command.
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Saves the accumulated history to `~/.sqldb_history`, if the user's
+    /// home directory could be determined. Errors are ignored, since a
+    /// failure to persist history should not prevent the shell from exiting.
+    pub fn save_history(&self) {
+        if let Some(path) = history_path() {
+            let _ = self.interface.save_history(&path);
+        }
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".sqldb_history"))
 }
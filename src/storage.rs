@@ -0,0 +1,539 @@
+//! # sqldb storage
+//!
+//! This module implements the table storage engine used by `sql::parser` to
+//! execute `INSERT`/`SELECT` queries. Rows have a fixed, hardcoded schema and
+//! are packed into fixed-size 4096-byte pages, mirroring the classic
+//! "db tutorial" storage layout.
+//!
+//! `Table` keeps its pages in memory for the lifetime of the process, while
+//! `Pager` backs the same page layout with a file on disk, loading pages
+//! lazily and flushing touched ones back on `.close`/`.exit`. `Store` starts
+//! out as an in-memory `Table` and swaps to a file-backed `Pager` when the
+//! user runs `.open FILENAME`; it implements `exec::ExecBackend`, which is
+//! the crate's default backend unless the `sqlite` feature is enabled.
+//!
+//! ## Examples
+//!
+//! ```rust
+//! use sqldb::storage::{Row, Table};
+//!
+//! let mut table = Table::new();
+//! let row = Row::new(1, "alice", "alice@example.com").unwrap();
+//! table.insert(&row).unwrap();
+//! table.select(None).unwrap();
+//! ```
+
+use crate::exec::{ExecBackend, QueryOutput};
+use crate::sql::parser::{CommandError, Statement};
+use std::convert::TryInto;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+/// Maximum number of bytes allowed for the `username` column.
+pub const COLUMN_USERNAME_SIZE: usize = 32;
+/// Maximum number of bytes allowed for the `email` column.
+pub const COLUMN_EMAIL_SIZE: usize = 255;
+
+const ID_SIZE: usize = std::mem::size_of::<u32>();
+const USERNAME_SIZE: usize = COLUMN_USERNAME_SIZE;
+const EMAIL_SIZE: usize = COLUMN_EMAIL_SIZE;
+
+const ID_OFFSET: usize = 0;
+const USERNAME_OFFSET: usize = ID_OFFSET + ID_SIZE;
+const EMAIL_OFFSET: usize = USERNAME_OFFSET + USERNAME_SIZE;
+
+/// The serialized size, in bytes, of a single `Row`.
+const ROW_SIZE: usize = ID_SIZE + USERNAME_SIZE + EMAIL_SIZE;
+
+/// The size, in bytes, of a single page.
+pub const PAGE_SIZE: usize = 4096;
+/// The number of rows that fit in a single page.
+pub const ROWS_PER_PAGE: usize = PAGE_SIZE / ROW_SIZE;
+/// The maximum number of pages a `Table` can hold.
+const TABLE_MAX_PAGES: usize = 100;
+/// The maximum number of rows a `Table` can hold.
+pub const TABLE_MAX_ROWS: usize = ROWS_PER_PAGE * TABLE_MAX_PAGES;
+
+/// A single row in the hardcoded `(id, username, email)` schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub id: u32,
+    pub username: [u8; COLUMN_USERNAME_SIZE],
+    pub email: [u8; COLUMN_EMAIL_SIZE],
+}
+
+/// Represents the possible errors that can occur when building a `Row`.
+#[derive(Debug)]
+pub enum RowError {
+    /// The `username` column value is longer than `COLUMN_USERNAME_SIZE`.
+    UsernameTooLong,
+    /// The `email` column value is longer than `COLUMN_EMAIL_SIZE`.
+    EmailTooLong,
+}
+
+impl std::fmt::Display for RowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RowError::UsernameTooLong => write!(
+                f,
+                "username is too long (max {} bytes)",
+                COLUMN_USERNAME_SIZE
+            ),
+            RowError::EmailTooLong => {
+                write!(f, "email is too long (max {} bytes)", COLUMN_EMAIL_SIZE)
+            }
+        }
+    }
+}
+
+impl Row {
+    /// Builds a `Row` from its column values, erroring when a value overflows
+    /// its fixed-size column.
+    pub fn new(id: u32, username: &str, email: &str) -> Result<Self, RowError> {
+        if username.len() > COLUMN_USERNAME_SIZE {
+            return Err(RowError::UsernameTooLong);
+        }
+        if email.len() > COLUMN_EMAIL_SIZE {
+            return Err(RowError::EmailTooLong);
+        }
+
+        let mut username_bytes = [0u8; COLUMN_USERNAME_SIZE];
+        username_bytes[..username.len()].copy_from_slice(username.as_bytes());
+
+        let mut email_bytes = [0u8; COLUMN_EMAIL_SIZE];
+        email_bytes[..email.len()].copy_from_slice(email.as_bytes());
+
+        Ok(Self {
+            id,
+            username: username_bytes,
+            email: email_bytes,
+        })
+    }
+
+    /// Returns the `username` column as a `String`, trimming the trailing
+    /// zero padding.
+    pub fn username_str(&self) -> String {
+        String::from_utf8_lossy(&self.username)
+            .trim_end_matches('\0')
+            .to_string()
+    }
+
+    /// Returns the `email` column as a `String`, trimming the trailing zero
+    /// padding.
+    pub fn email_str(&self) -> String {
+        String::from_utf8_lossy(&self.email)
+            .trim_end_matches('\0')
+            .to_string()
+    }
+
+    /// Returns the value of the column named `name` (`"id"`, `"username"`,
+    /// or `"email"`), or `None` if `name` isn't one of them.
+    pub fn column(&self, name: &str) -> Option<String> {
+        match name {
+            "id" => Some(self.id.to_string()),
+            "username" => Some(self.username_str()),
+            "email" => Some(self.email_str()),
+            _ => None,
+        }
+    }
+
+    /// Formats `row` as `(a, b, c)`, restricted to `projection` in the order
+    /// given, or the full `(id, username, email)` row when `projection` is
+    /// `None`. Errors with the offending column name if `projection` names a
+    /// column that isn't `id`, `username`, or `email`.
+    pub(crate) fn format_projected(&self, projection: Option<&[String]>) -> Result<String, String> {
+        match projection {
+            None => Ok(format!(
+                "({}, {}, {})",
+                self.id,
+                self.username_str(),
+                self.email_str()
+            )),
+            Some(columns) => {
+                let values = columns
+                    .iter()
+                    .map(|name| self.column(name).ok_or_else(|| name.clone()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(format!("({})", values.join(", ")))
+            }
+        }
+    }
+}
+
+/// Packs `row` into `destination`, which must be at least `ROW_SIZE` bytes.
+pub fn serialize_row(row: &Row, destination: &mut [u8]) {
+    destination[ID_OFFSET..ID_OFFSET + ID_SIZE].copy_from_slice(&row.id.to_ne_bytes());
+    destination[USERNAME_OFFSET..USERNAME_OFFSET + USERNAME_SIZE].copy_from_slice(&row.username);
+    destination[EMAIL_OFFSET..EMAIL_OFFSET + EMAIL_SIZE].copy_from_slice(&row.email);
+}
+
+/// Unpacks a `Row` out of `source`, which must be at least `ROW_SIZE` bytes.
+pub fn deserialize_row(source: &[u8]) -> Row {
+    let id = u32::from_ne_bytes(
+        source[ID_OFFSET..ID_OFFSET + ID_SIZE]
+            .try_into()
+            .expect("row slice is ROW_SIZE bytes"),
+    );
+
+    let mut username = [0u8; COLUMN_USERNAME_SIZE];
+    username.copy_from_slice(&source[USERNAME_OFFSET..USERNAME_OFFSET + USERNAME_SIZE]);
+
+    let mut email = [0u8; COLUMN_EMAIL_SIZE];
+    email.copy_from_slice(&source[EMAIL_OFFSET..EMAIL_OFFSET + EMAIL_SIZE]);
+
+    Row {
+        id,
+        username,
+        email,
+    }
+}
+
+/// Represents the possible errors that can occur when operating on a `Table`.
+#[derive(Debug)]
+pub enum TableError {
+    /// The table has reached `TABLE_MAX_ROWS` and cannot accept more rows.
+    TableFull,
+    /// Reading or writing a page to the backing file failed.
+    Io(io::Error),
+    /// A `SELECT` projection named a column that isn't `id`, `username`, or
+    /// `email`.
+    UnknownColumn(String),
+}
+
+impl std::fmt::Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableError::TableFull => write!(f, "table is full"),
+            TableError::Io(err) => write!(f, "I/O error: {}", err),
+            TableError::UnknownColumn(name) => write!(f, "unknown column '{}'", name),
+        }
+    }
+}
+
+/// Maps a `TableError` onto the `CommandError` an `ExecBackend` reports,
+/// preserving `TableFull` as its own variant and folding the rest into
+/// `ExecutionFailed`.
+fn map_table_error(err: TableError) -> CommandError {
+    match err {
+        TableError::TableFull => CommandError::TableFull,
+        TableError::Io(_) | TableError::UnknownColumn(_) => {
+            CommandError::ExecutionFailed(err.to_string())
+        }
+    }
+}
+
+/// An in-memory table of rows, backed by fixed-size pages.
+pub struct Table {
+    pages: Vec<Box<[u8; PAGE_SIZE]>>,
+    pub num_rows: usize,
+}
+
+impl Table {
+    /// Creates a new, empty `Table`.
+    pub fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            num_rows: 0,
+        }
+    }
+
+    /// Returns a mutable slice pointing at the storage slot for `row_num`,
+    /// allocating pages as needed.
+    fn row_slot(&mut self, row_num: usize) -> &mut [u8] {
+        let page_num = row_num / ROWS_PER_PAGE;
+        while self.pages.len() <= page_num {
+            self.pages.push(Box::new([0u8; PAGE_SIZE]));
+        }
+
+        let row_offset = (row_num % ROWS_PER_PAGE) * ROW_SIZE;
+        &mut self.pages[page_num][row_offset..row_offset + ROW_SIZE]
+    }
+
+    /// Appends `row` at the next free slot.
+    pub fn insert(&mut self, row: &Row) -> Result<(), TableError> {
+        if self.num_rows >= TABLE_MAX_ROWS {
+            return Err(TableError::TableFull);
+        }
+
+        let slot = self.row_slot(self.num_rows);
+        serialize_row(row, slot);
+        self.num_rows += 1;
+
+        Ok(())
+    }
+
+    /// Prints every row currently stored in the table, restricted to
+    /// `projection` if given.
+    pub fn select(&self, projection: Option<&[String]>) -> Result<(), TableError> {
+        for row_num in 0..self.num_rows {
+            let page_num = row_num / ROWS_PER_PAGE;
+            let row_offset = (row_num % ROWS_PER_PAGE) * ROW_SIZE;
+            let row = deserialize_row(&self.pages[page_num][row_offset..row_offset + ROW_SIZE]);
+            println!(
+                "{}",
+                row.format_projected(projection)
+                    .map_err(TableError::UnknownColumn)?
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A file-backed table that lazily loads 4096-byte pages from disk on
+/// demand, keeping only touched pages resident in memory.
+pub struct Pager {
+    file: File,
+    pages: Vec<Option<Box<[u8; PAGE_SIZE]>>>,
+    pub num_rows: usize,
+}
+
+impl Pager {
+    /// Opens (creating if necessary) the file at `path` and recovers
+    /// `num_rows` from its length.
+    pub fn open(path: &str) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        let file_length = file.metadata()?.len() as usize;
+        let num_rows = file_length / ROW_SIZE;
+        let num_pages = file_length.div_ceil(PAGE_SIZE);
+
+        Ok(Self {
+            file,
+            pages: (0..num_pages).map(|_| None).collect(),
+            num_rows,
+        })
+    }
+
+    /// Returns the page holding `page_num`, loading it from disk the first
+    /// time it's touched.
+    fn get_page(&mut self, page_num: usize) -> io::Result<&mut [u8; PAGE_SIZE]> {
+        if page_num >= self.pages.len() {
+            self.pages.resize_with(page_num + 1, || None);
+        }
+
+        if self.pages[page_num].is_none() {
+            let mut page = Box::new([0u8; PAGE_SIZE]);
+
+            let file_length = self.file.metadata()?.len();
+            let page_start = (page_num * PAGE_SIZE) as u64;
+            if page_start < file_length {
+                let read_len = std::cmp::min(PAGE_SIZE as u64, file_length - page_start) as usize;
+                self.file.seek(SeekFrom::Start(page_start))?;
+                self.file.read_exact(&mut page[..read_len])?;
+            }
+
+            self.pages[page_num] = Some(page);
+        }
+
+        Ok(self.pages[page_num].as_mut().expect("page was just loaded"))
+    }
+
+    /// Appends `row` at the next free slot, loading its page first.
+    pub fn insert(&mut self, row: &Row) -> Result<(), TableError> {
+        if self.num_rows >= TABLE_MAX_ROWS {
+            return Err(TableError::TableFull);
+        }
+
+        let row_num = self.num_rows;
+        let page_num = row_num / ROWS_PER_PAGE;
+        let row_offset = (row_num % ROWS_PER_PAGE) * ROW_SIZE;
+
+        let page = self.get_page(page_num).map_err(TableError::Io)?;
+        serialize_row(row, &mut page[row_offset..row_offset + ROW_SIZE]);
+        self.num_rows += 1;
+
+        Ok(())
+    }
+
+    /// Prints every row currently stored in the file, restricted to
+    /// `projection` if given.
+    pub fn select(&mut self, projection: Option<&[String]>) -> Result<(), TableError> {
+        for row_num in 0..self.num_rows {
+            let page_num = row_num / ROWS_PER_PAGE;
+            let row_offset = (row_num % ROWS_PER_PAGE) * ROW_SIZE;
+
+            let page = self.get_page(page_num).map_err(TableError::Io)?;
+            let row = deserialize_row(&page[row_offset..row_offset + ROW_SIZE]);
+            println!(
+                "{}",
+                row.format_projected(projection)
+                    .map_err(TableError::UnknownColumn)?
+            );
+        }
+        Ok(())
+    }
+
+    /// Flushes every page touched by a stored row back to disk, truncating
+    /// the last page to only the bytes actually used.
+    pub fn flush(&mut self) -> io::Result<()> {
+        let num_full_pages = self.num_rows / ROWS_PER_PAGE;
+
+        for page_num in 0..num_full_pages {
+            self.flush_page(page_num, PAGE_SIZE)?;
+        }
+
+        let remaining_rows = self.num_rows % ROWS_PER_PAGE;
+        if remaining_rows > 0 {
+            self.flush_page(num_full_pages, remaining_rows * ROW_SIZE)?;
+        }
+
+        self.file.flush()
+    }
+
+    fn flush_page(&mut self, page_num: usize, size: usize) -> io::Result<()> {
+        if let Some(page) = self.pages.get(page_num).and_then(|page| page.as_ref()) {
+            self.file
+                .seek(SeekFrom::Start((page_num * PAGE_SIZE) as u64))?;
+            self.file.write_all(&page[..size])?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The active storage backend: a transient in-memory `Table`, or a
+/// file-backed `Pager` after `.open FILENAME`.
+pub enum Store {
+    Memory(Table),
+    File(Pager),
+}
+
+impl Store {
+    /// Inserts `row` into whichever backend is currently active.
+    pub fn insert(&mut self, row: &Row) -> Result<(), TableError> {
+        match self {
+            Store::Memory(table) => table.insert(row),
+            Store::File(pager) => pager.insert(row),
+        }
+    }
+
+    /// Prints every row from whichever backend is currently active,
+    /// restricted to `projection` if given.
+    pub fn select(&mut self, projection: Option<&[String]>) -> Result<(), TableError> {
+        match self {
+            Store::Memory(table) => table.select(projection),
+            Store::File(pager) => pager.select(projection),
+        }
+    }
+
+    /// Flushes dirty pages to disk if the active backend is file-backed; a
+    /// no-op for the in-memory backend.
+    pub fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Store::Memory(_) => Ok(()),
+            Store::File(pager) => pager.flush(),
+        }
+    }
+}
+
+impl Default for Store {
+    /// Defaults to the transient in-memory backend.
+    fn default() -> Self {
+        Store::Memory(Table::new())
+    }
+}
+
+impl ExecBackend for Store {
+    fn execute(&mut self, stmt: &Statement) -> Result<QueryOutput, CommandError> {
+        match stmt {
+            Statement::Insert(row) => self
+                .insert(row)
+                .map(|()| QueryOutput::Inserted)
+                .map_err(map_table_error),
+            Statement::Select(projection) => {
+                self.select(projection.as_deref())
+                    .map_err(map_table_error)?;
+                Ok(QueryOutput::Selected)
+            }
+        }
+    }
+
+    fn open(&mut self, path: &str) -> Result<(), CommandError> {
+        if let Store::File(pager) = self {
+            pager
+                .flush()
+                .map_err(|err| CommandError::OpenFailed(err.to_string()))?;
+        }
+        let pager = Pager::open(path).map_err(|err| CommandError::OpenFailed(err.to_string()))?;
+        *self = Store::File(pager);
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), CommandError> {
+        if let Store::File(pager) = self {
+            pager
+                .flush()
+                .map_err(|err| CommandError::OpenFailed(err.to_string()))?;
+        }
+        *self = Store::default();
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), CommandError> {
+        Store::flush(self).map_err(|err| CommandError::OpenFailed(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_deserialize_round_trip() {
+        let row = Row::new(7, "alice", "alice@example.com").unwrap();
+        let mut bytes = [0u8; ROW_SIZE];
+
+        serialize_row(&row, &mut bytes);
+        let round_tripped = deserialize_row(&bytes);
+
+        assert_eq!(round_tripped, row);
+    }
+
+    #[test]
+    fn serialize_deserialize_round_trip_empty_columns() {
+        let row = Row::new(0, "", "").unwrap();
+        let mut bytes = [0u8; ROW_SIZE];
+
+        serialize_row(&row, &mut bytes);
+        let round_tripped = deserialize_row(&bytes);
+
+        assert_eq!(round_tripped, row);
+    }
+
+    #[test]
+    fn pager_recovers_num_rows_across_restart() {
+        let path = std::env::temp_dir().join(format!(
+            "sqldb_pager_restart_test_{}.db",
+            std::process::id()
+        ));
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+
+        {
+            let mut pager = Pager::open(path).unwrap();
+            assert_eq!(pager.num_rows, 0);
+
+            for id in 0..3 {
+                let row = Row::new(id, "alice", "alice@example.com").unwrap();
+                pager.insert(&row).unwrap();
+            }
+            pager.flush().unwrap();
+        }
+
+        let pager = Pager::open(path).unwrap();
+        assert_eq!(pager.num_rows, 3);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
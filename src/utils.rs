@@ -4,10 +4,8 @@
 //!
 //! ## Usage
 //!
-//! The module exposes functions to retrieve program name, version, print the prompt,
-//! and print database details.
-//!
-//! The `print_prompt()` function prints the prompt for user input.
+//! The module exposes functions to retrieve program name, version, and print
+//! database details.
 //!
 //! The `print_db_details()` function prints the database details, including the database name, version,
 //! current time, usage hints, and connection status.
@@ -15,53 +13,25 @@
 //! ## Examples
 //!
 //! ```rust
-//! use sqldb::utils::{print_db_details, print_prompt};
+//! use sqldb::utils::print_db_details;
 //!
-//! fn main() -> std::io::Result<()> {
+//! fn main() {
 //!     print_db_details();
-//!     print_prompt()?;
-//!     Ok(())
 //! }
 //! ```
 
 use chrono::Local;
 use once_cell::sync::Lazy;
 use std::fs;
-use std::io::{self, Write};
 use toml::Value;
 
 // Metadata struct holding the program name and version.
-struct Metadata {
-    name: String,
-    version: String,
+pub(crate) struct Metadata {
+    pub(crate) name: String,
+    pub(crate) version: String,
 }
 
-static METADATA: Lazy<Metadata> = Lazy::new(retrieve_metadata);
-
-/// Prints the prompt for user input.
-///
-/// This function prints the prompt for user input, which includes the program name followed by `>`.
-///
-/// # Errors
-///
-/// Returns an `std::io::Result` indicating whether the prompt was printed successfully or if an error
-/// occurred during the output operation.
-///
-/// # Examples
-///
-/// ```rust
-/// use sqldb::utils::print_prompt;
-///
-/// fn main() -> std::io::Result<()> {
-///     print_prompt()?;
-///     Ok(())
-/// }
-/// ```
-pub fn print_prompt() -> io::Result<()> {
-    print!("{} > ", METADATA.name);
-    io::stdout().flush()?;
-    Ok(())
-}
+pub(crate) static METADATA: Lazy<Metadata> = Lazy::new(retrieve_metadata);
 
 /// Prints the database details.
 ///